@@ -0,0 +1,132 @@
+use std::{env, fs, path::PathBuf};
+
+use serde::Deserialize;
+
+use crate::redact::DEFAULT_DENYLIST;
+
+/// Runtime configuration for the server, loaded from a YAML file. Any field
+/// missing from the file falls back to [`Config::default`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Address the HTTP server binds to, e.g. `0.0.0.0:3000`.
+    pub bind_addr: String,
+    /// Optional path to an external minijinja template, overriding the
+    /// built-in `HTML` constant when set.
+    pub template_path: Option<PathBuf>,
+    /// Substrings (case-insensitive) that mark an environment variable's
+    /// key as sensitive for [`crate::redact::redact_envs`].
+    pub redact_keys: Vec<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            bind_addr: "0.0.0.0:3000".to_string(),
+            template_path: None,
+            redact_keys: DEFAULT_DENYLIST.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+impl Config {
+    /// Loads configuration from the YAML file named by `CONFIG_FILE`
+    /// (defaulting to `config.yml`), falling back to [`Config::default`]
+    /// when the file doesn't exist. `REDACT_KEYS`, if set to a non-empty
+    /// value, overrides whatever denylist the file specified.
+    pub fn load() -> Self {
+        let path = env::var("CONFIG_FILE").unwrap_or_else(|_| "config.yml".to_string());
+        let mut config = match fs::read_to_string(&path) {
+            Ok(contents) => serde_yaml::from_str(&contents)
+                .unwrap_or_else(|err| panic!("failed to parse {path}: {err}")),
+            Err(_) => Config::default(),
+        };
+
+        if let Ok(value) = env::var("REDACT_KEYS") {
+            let redact_keys: Vec<String> = value
+                .split(',')
+                .map(|needle| needle.trim().to_string())
+                .filter(|needle| !needle.is_empty())
+                .collect();
+            if !redact_keys.is_empty() {
+                config.redact_keys = redact_keys;
+            }
+        }
+
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    // `Config::load` reads process-global env vars, so tests that set them
+    // must not run concurrently with each other.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    fn with_locked_env(test: impl FnOnce()) {
+        let _guard = ENV_LOCK.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        env::remove_var("CONFIG_FILE");
+        env::remove_var("REDACT_KEYS");
+        test();
+        env::remove_var("CONFIG_FILE");
+        env::remove_var("REDACT_KEYS");
+    }
+
+    #[test]
+    fn default_has_the_default_denylist() {
+        let config = Config::default();
+        assert_eq!(config.bind_addr, "0.0.0.0:3000");
+        assert_eq!(config.template_path, None);
+        assert_eq!(
+            config.redact_keys,
+            DEFAULT_DENYLIST.iter().map(|s| s.to_string()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn load_falls_back_to_default_when_the_file_is_absent() {
+        with_locked_env(|| {
+            env::set_var("CONFIG_FILE", "/nonexistent/k8s-rust-web-config.yml");
+            assert_eq!(Config::load().bind_addr, Config::default().bind_addr);
+        });
+    }
+
+    #[test]
+    fn load_applies_fields_present_in_the_file() {
+        with_locked_env(|| {
+            let path = std::env::temp_dir().join(format!(
+                "k8s-rust-web-config-test-{:?}.yml",
+                std::thread::current().id()
+            ));
+            fs::write(&path, "bind_addr: \"127.0.0.1:8080\"\n").unwrap();
+            env::set_var("CONFIG_FILE", &path);
+
+            let config = Config::load();
+
+            assert_eq!(config.bind_addr, "127.0.0.1:8080");
+            assert_eq!(config.redact_keys, Config::default().redact_keys);
+
+            fs::remove_file(&path).ok();
+        });
+    }
+
+    #[test]
+    fn redact_keys_env_var_overrides_the_default_denylist() {
+        with_locked_env(|| {
+            env::set_var("REDACT_KEYS", "FOO,BAR");
+            assert_eq!(Config::load().redact_keys, vec!["FOO".to_string(), "BAR".to_string()]);
+        });
+    }
+
+    #[test]
+    fn blank_redact_keys_env_var_keeps_the_default_denylist() {
+        with_locked_env(|| {
+            env::set_var("REDACT_KEYS", "");
+            assert_eq!(Config::load().redact_keys, Config::default().redact_keys);
+        });
+    }
+}
@@ -1,14 +1,32 @@
-use std::{collections::HashMap, env};
+use std::{collections::HashMap, env, sync::atomic::Ordering};
 
 use axum::{
+    extract::{Request, State},
+    middleware,
     routing::get,
     http::StatusCode,
-    Json, Router,
+    Extension, Json, Router,
     response::Html,
 };
 
 use minijinja::render;
-use local_ip_address::local_ip;
+use tokio::signal::unix::{signal, SignalKind};
+use tower_http::{
+    services::ServeDir,
+    trace::{DefaultOnRequest, DefaultOnResponse, MakeSpan, TraceLayer},
+};
+use tracing::{Level, Span};
+use uuid::Uuid;
+
+mod config;
+mod metrics;
+mod redact;
+mod state;
+
+use config::Config;
+use metrics::{get_metrics, Metrics};
+use redact::redact_envs;
+use state::AppState;
 
 #[tokio::main]
 /// The main entry point for the application.
@@ -18,58 +36,140 @@ use local_ip_address::local_ip;
 ///
 /// - `GET /`: Renders an HTML page with environment variables.
 /// - `GET /health`: Returns a simple "OK" response.
+/// - `GET /livez`: Liveness probe; OK once the process is up.
+/// - `GET /readyz`: Readiness probe; 503 until startup completes and during
+///   shutdown drain.
 /// - `GET /api`: Returns a JSON response with all the environment variables.
+/// - `GET /metrics`: Renders `http_requests_total` and
+///   `http_request_duration_seconds` in Prometheus text format.
+/// - `GET /static/*`: Serves the `assets/` directory, so the page no longer
+///   depends on a CDN.
 ///
 /// The server uses the `tracing` crate for logging, and the `minijinja` crate for
-/// rendering the HTML template.
+/// rendering the HTML template. A `TraceLayer` logs a "started processing
+/// request"/"finished processing request" pair per request inside a `request`
+/// span tagged with a UUID. A second middleware layer records
+/// request counts and latency for the `/metrics` endpoint. The host's name,
+/// local IP, and a [`Config`] loaded from YAML (whose `bind_addr` replaces the
+/// default `0.0.0.0:3000`) are resolved once into an
+/// [`AppState`] shared via `State`. A SIGTERM handler flips `AppState::ready`
+/// to `false` and drives a graceful shutdown.
 async fn main() {
     // initialize tracing
     tracing_subscriber::fmt::init();
 
+    let metrics = Metrics::new();
+    let config = Config::load();
+    let bind_addr = config.bind_addr.clone();
+    let app_state = AppState::new(config);
+    let ready = app_state.ready.clone();
+
     // build our application with a route
     let app = Router::new()
         // `GET /` goes to `root`
         .route("/", get(root))
         .route("/health", get(get_health))
-        .route("/api", get(get_env));
+        .route("/livez", get(get_livez))
+        .route("/readyz", get(get_readyz))
+        .route("/api", get(get_env))
+        .route("/metrics", get(get_metrics))
+        .nest_service("/static", ServeDir::new("assets"))
+        .layer(middleware::from_fn(metrics::track_metrics))
+        .layer(Extension(metrics))
+        .layer(
+            TraceLayer::new_for_http()
+                .make_span_with(RequestIdMakeSpan)
+                .on_request(DefaultOnRequest::new().level(Level::INFO))
+                .on_response(DefaultOnResponse::new().level(Level::INFO)),
+        )
+        .with_state(app_state);
 
-    // run our app with hyper, listening globally on port 3000
-    let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    // run our app with hyper, listening on the configured bind address
+    let listener = tokio::net::TcpListener::bind(&bind_addr).await.unwrap();
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal(ready))
+        .await
+        .unwrap();
 }
 
+/// How long to wait, after `/readyz` starts failing, before letting
+/// `axum::serve` stop accepting new connections. Covers the readiness-probe
+/// period plus Kubernetes' endpoint-propagation lag, so a connection
+/// in-flight to the Service isn't routed to a pod that's already draining.
+const SHUTDOWN_GRACE_PERIOD: std::time::Duration = std::time::Duration::from_secs(5);
 
-/// Renders the root page of the application.
+/// Waits for SIGTERM, flips `ready` to not-ready so `/readyz` starts failing,
+/// waits out [`SHUTDOWN_GRACE_PERIOD`] for Kubernetes to remove the pod's
+/// Service endpoint, then returns so `axum::serve`'s graceful shutdown can
+/// stop accepting connections and drain any in-flight requests.
+async fn shutdown_signal(ready: std::sync::Arc<std::sync::atomic::AtomicBool>) {
+    signal(SignalKind::terminate())
+        .expect("failed to install SIGTERM handler")
+        .recv()
+        .await;
+    tracing::info!("received SIGTERM, failing readiness checks and waiting to drain");
+    ready.store(false, Ordering::SeqCst);
+    tokio::time::sleep(SHUTDOWN_GRACE_PERIOD).await;
+}
+
+/// Builds the per-request span used by the `TraceLayer`.
 ///
-/// This function sets the `HOSTNAME` and `LOCAL_IP` environment variables, collects
-/// all the environment variables into a `HashMap`, and then renders an HTML template
-/// using the `minijinja` crate. The rendered HTML is returned with a `StatusCode::OK`.
+/// Each request is assigned a fresh UUID as `request_id`, recorded with the
+/// method and URI on the `request` span. That span wraps both the "started
+/// processing request" event `TraceLayer` logs on entry and the "finished
+/// processing request" event (with `latency` and `status`) it logs on
+/// completion, so the pair can be correlated even under concurrent load.
+#[derive(Clone, Copy, Default)]
+struct RequestIdMakeSpan;
+
+impl<B> MakeSpan<B> for RequestIdMakeSpan {
+    fn make_span(&mut self, request: &Request<B>) -> Span {
+        tracing::info_span!(
+            "request",
+            method = %request.method(),
+            uri = %request.uri(),
+            request_id = %Uuid::new_v4(),
+        )
+    }
+}
+
+/// Builds the context for both the HTML template and the JSON response: the
+/// process environment with denylisted values redacted, plus the cached
+/// HOSTNAME/LOCAL_IP/UPTIME_SECONDS.
+fn cached_context(state: &AppState) -> HashMap<String, String> {
+    let mut envs = redact_envs(env::vars().collect(), &state.config.redact_keys);
+    envs.insert("HOSTNAME".to_string(), state.hostname.clone());
+    envs.insert(
+        "LOCAL_IP".to_string(),
+        state
+            .local_ip
+            .map(|ip| ip.to_string())
+            .unwrap_or_else(|| "unknown".to_string()),
+    );
+    envs.insert(
+        "UPTIME_SECONDS".to_string(),
+        state.started_at.elapsed().as_secs().to_string(),
+    );
+    envs
+}
+
+/// Renders the root page of the application.
 ///
 /// # Returns
 /// A tuple containing the `StatusCode::OK` and the rendered HTML as a `String`.
-async fn root() -> (StatusCode, Html<String>) {
-    tracing::info!("GET /");
-    env::set_var("HOSTNAME", gethostname::gethostname().to_string_lossy().to_string());
-    let my_local_ip = local_ip().unwrap();
-    env::set_var("LOCAL_IP", my_local_ip.to_string());
-    let envs: HashMap<String, String> = env::vars().collect();
-    let rendered = render!(HTML, envs);
-    
+async fn root(State(state): State<AppState>) -> (StatusCode, Html<String>) {
+    let envs = cached_context(&state);
+    let rendered = render!(&state.template, envs);
+
     (StatusCode::OK, Html(rendered.to_string()))
 }
 
 /// Returns a JSON response containing all the environment variables as a HashMap.
 ///
-/// This function collects all the environment variables into a HashMap and returns
-/// them as a JSON response. This can be used to retrieve information about the
-/// runtime environment of the application.
-///
 /// # Returns
 /// A JSON response containing a HashMap of all the environment variables.
-async fn get_env() -> Json<HashMap<String, String>> {
-    tracing::info!("GET /api");
-    let envs: HashMap<String, String> = env::vars().collect();
-    Json(envs)
+async fn get_env(State(state): State<AppState>) -> Json<HashMap<String, String>> {
+    Json(cached_context(&state))
 }
 
 
@@ -79,19 +179,35 @@ async fn get_env() -> Json<HashMap<String, String>> {
 /// The status code will be `StatusCode::OK` (200) if the application is healthy, and the string will be "OK".
 /// This function is intended to be used for health checks, such as by a load balancer or monitoring system.
 async fn get_health() -> (StatusCode, &'static str) {
-    tracing::info!("GET /health");
     (StatusCode::OK, "OK")
 }
 
+/// Liveness probe: always `StatusCode::OK` once the process is serving
+/// requests. Kubernetes restarts the pod if this stops responding.
+async fn get_livez() -> (StatusCode, &'static str) {
+    (StatusCode::OK, "OK")
+}
+
+/// Readiness probe: `StatusCode::OK` while `AppState::ready` is set, and
+/// `StatusCode::SERVICE_UNAVAILABLE` during shutdown drain. Kubernetes stops
+/// routing traffic to the pod while this fails.
+async fn get_readyz(State(state): State<AppState>) -> (StatusCode, &'static str) {
+    if state.ready.load(Ordering::SeqCst) {
+        (StatusCode::OK, "OK")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "not ready")
+    }
+}
+
 
 /// The HTML template used by the application.
-const HTML: &'static str = r#"
+pub(crate) const HTML: &'static str = r#"
 <!DOCTYPE html>
 <html>
 <head>
     <title>Welcome {{envs.HOSTNAME}}</title>
-    <!-- Add Bootstrap CSS link -->
-    <link rel="stylesheet" href="https://cdn.jsdelivr.net/npm/bootstrap@5.3.0/dist/css/bootstrap.min.css">
+    <!-- Served locally from assets/, no CDN required -->
+    <link rel="stylesheet" href="/static/style.css">
 </head>
 <body>
     <div class="container text-center">
@@ -119,23 +235,8 @@ const HTML: &'static str = r#"
         </table>
     </div>
 
-    <!-- Add Bootstrap JS scripts -->
-    <script src="https://cdn.jsdelivr.net/npm/bootstrap@5.3.0/dist/js/bootstrap.bundle.min.js"></script>
 </body>
-<script>
-    // Rainbow text easing
-    var colors = ['#FF0000', '#FF7F00', '#FFFF00', '#00FF00', '#0000FF', '#4B0082', '#9400D3'];
-    var i = 0;
-    setInterval(function() {
-        document.querySelector('.rainbow').style.color = colors[i];
-        i = (i + 1) % colors.length;
-        document.querySelector('.rainbow').style.transition = 'color 2s';
-        document.querySelector('.rainbow').style.transitionTimingFunction = 'ease';
-        document.querySelector('.rainbow').style.transitionDuration = '2s';
-        document.querySelector('.rainbow').style.transitionDelay = '0s';
-    }, 1000);
-
-</script>
+<script src="/static/app.js"></script>
 </html>
 "#;
 
@@ -150,7 +251,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_root() {
-        let app = Router::new().route("/", get(root));
+        let app = Router::new()
+            .route("/", get(root))
+            .with_state(AppState::new(Config::default()));
 
         let response = app
             .into_service()
@@ -167,7 +270,9 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_env() {
-        let app = Router::new().route("/api", get(get_env));
+        let app = Router::new()
+            .route("/api", get(get_env))
+            .with_state(AppState::new(Config::default()));
 
         let response = app
             .oneshot(Request::builder().uri("/api").body(Body::empty()).unwrap())
@@ -198,4 +303,49 @@ mod tests {
         assert_eq!(response.status(), StatusCode::OK);
     }
 
-}
\ No newline at end of file
+    #[tokio::test]
+    async fn test_get_livez() {
+        let app = Router::new().route("/livez", get(get_livez));
+
+        let response = app
+            .oneshot(Request::builder().uri("/livez").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_get_static_asset() {
+        let app = Router::new().nest_service("/static", ServeDir::new("assets"));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/static/style.css")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_get_readyz_fails_after_ready_is_cleared() {
+        let state = AppState::new(Config::default());
+        state.ready.store(false, Ordering::SeqCst);
+        let app = Router::new()
+            .route("/readyz", get(get_readyz))
+            .with_state(state);
+
+        let response = app
+            .oneshot(Request::builder().uri("/readyz").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+}
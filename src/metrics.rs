@@ -0,0 +1,174 @@
+use std::time::Instant;
+
+use axum::{
+    extract::{MatchedPath, Request},
+    http::StatusCode,
+    middleware::Next,
+    response::Response,
+    Extension,
+};
+use opentelemetry::{global, metrics::{Counter, Histogram}, KeyValue};
+use opentelemetry_sdk::metrics::{Aggregation, Instrument, SdkMeterProvider, Stream};
+use prometheus::{Encoder, Registry, TextEncoder};
+
+/// Seconds-scale histogram buckets for `http_request_duration_seconds`.
+/// `request_duration.record` is fed fractional seconds, so the SDK's
+/// default millisecond-scale buckets would bucket nearly every request
+/// together.
+const DURATION_BUCKETS_SECONDS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+fn duration_histogram_view(instrument: &Instrument) -> Option<Stream> {
+    if instrument.name == "http_request_duration_seconds" {
+        Some(
+            Stream::new()
+                .name(instrument.name.clone())
+                .aggregation(Aggregation::ExplicitBucketHistogram {
+                    boundaries: DURATION_BUCKETS_SECONDS.to_vec(),
+                    record_min_max: true,
+                }),
+        )
+    } else {
+        None
+    }
+}
+
+/// Prometheus/OpenTelemetry metrics shared between the metrics middleware and
+/// the `GET /metrics` handler.
+///
+/// An `opentelemetry_sdk` meter provider records the instruments, and is
+/// bridged to a `prometheus::Registry` so it can be scraped in Prometheus
+/// text exposition format without running a full OTLP collector.
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    requests_total: Counter<u64>,
+    request_duration: Histogram<f64>,
+}
+
+impl Metrics {
+    /// Builds the meter provider and registers `http_requests_total` and
+    /// `http_request_duration_seconds` against a dedicated Prometheus
+    /// registry.
+    ///
+    /// The counter instrument is named `http_requests` (not
+    /// `http_requests_total`): `opentelemetry_prometheus` appends its own
+    /// `_total` suffix to counters on export, so naming the instrument
+    /// `http_requests_total` here would expose it as
+    /// `http_requests_total_total`.
+    pub fn new() -> Self {
+        let registry = Registry::new();
+        let exporter = opentelemetry_prometheus::exporter()
+            .with_registry(registry.clone())
+            .build()
+            .expect("failed to build the Prometheus exporter");
+        let provider = SdkMeterProvider::builder()
+            .with_reader(exporter)
+            .with_view(duration_histogram_view)
+            .build();
+        global::set_meter_provider(provider);
+
+        let meter = global::meter("k8s-rust-web");
+        let requests_total = meter
+            .u64_counter("http_requests")
+            .with_description("Total number of HTTP requests handled")
+            .init();
+        let request_duration = meter
+            .f64_histogram("http_request_duration_seconds")
+            .with_description("HTTP request latency in seconds")
+            .init();
+
+        Metrics {
+            registry,
+            requests_total,
+            request_duration,
+        }
+    }
+
+    /// Renders the registry in Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let metric_families = self.registry.gather();
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&metric_families, &mut buffer)
+            .expect("failed to encode Prometheus metrics");
+        String::from_utf8(buffer).expect("Prometheus metrics are valid UTF-8")
+    }
+}
+
+/// Tower middleware that records `http_requests_total` and
+/// `http_request_duration_seconds` for every request, labelled by route and
+/// status code.
+pub async fn track_metrics(
+    Extension(metrics): Extension<Metrics>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_owned())
+        .unwrap_or_else(|| request.uri().path().to_owned());
+
+    let started_at = Instant::now();
+    let response = next.run(request).await;
+    let latency = started_at.elapsed().as_secs_f64();
+    let status = response.status();
+
+    let labels = [
+        KeyValue::new("route", route),
+        KeyValue::new("status", status_label(status)),
+    ];
+    metrics.requests_total.add(1, &labels);
+    metrics.request_duration.record(latency, &labels);
+
+    response
+}
+
+/// Renders the Prometheus metrics registry as a text-format response.
+pub async fn get_metrics(Extension(metrics): Extension<Metrics>) -> String {
+    metrics.render()
+}
+
+fn status_label(status: StatusCode) -> String {
+    status.as_u16().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn get_metrics_renders_the_requests_total_counter() {
+        let metrics = Metrics::new();
+        metrics.requests_total.add(1, &[KeyValue::new("route", "/"), KeyValue::new("status", "200")]);
+
+        let app = Router::new()
+            .route("/metrics", get(get_metrics))
+            .layer(Extension(metrics));
+
+        let response = app
+            .oneshot(Request::builder().uri("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(
+            body.lines().any(|line| {
+                line.starts_with("http_requests_total{") && line.contains(r#"route="/""#)
+            }),
+            "expected an exact http_requests_total{{...}} series, got:\n{body}"
+        );
+        assert!(
+            !body.contains("http_requests_total_total"),
+            "counter instrument must not double the _total suffix"
+        );
+    }
+}
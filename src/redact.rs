@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+/// The denylist substrings used when no configuration overrides them.
+/// Matching is case-insensitive and checks the variable's *key*, not its
+/// value, so both `AWS_SECRET_ACCESS_KEY` and `aws_secret_access_key` are
+/// caught. A key that contains none of these substrings, such as
+/// `DATABASE_URL`, is not redacted.
+pub const DEFAULT_DENYLIST: &[&str] = &["SECRET", "TOKEN", "PASSWORD", "KEY", "_PASS"];
+
+/// The value substituted for anything matching the denylist.
+const REDACTED: &str = "***REDACTED***";
+
+/// Masks the values of environment variables whose keys match `denylist`.
+pub fn redact_envs(envs: HashMap<String, String>, denylist: &[String]) -> HashMap<String, String> {
+    envs.into_iter()
+        .map(|(key, value)| {
+            let key_upper = key.to_uppercase();
+            if denylist.iter().any(|needle| key_upper.contains(&needle.to_uppercase())) {
+                (key, REDACTED.to_string())
+            } else {
+                (key, value)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn redacts_keys_matching_the_denylist() {
+        let mut envs = HashMap::new();
+        envs.insert("AWS_SECRET_ACCESS_KEY".to_string(), "shh".to_string());
+        envs.insert("HOSTNAME".to_string(), "web-1".to_string());
+        let denylist: Vec<String> = DEFAULT_DENYLIST.iter().map(|s| s.to_string()).collect();
+
+        let redacted = redact_envs(envs, &denylist);
+
+        assert_eq!(redacted["AWS_SECRET_ACCESS_KEY"], REDACTED);
+        assert_eq!(redacted["HOSTNAME"], "web-1");
+    }
+}
@@ -0,0 +1,46 @@
+use std::{
+    fs,
+    net::IpAddr,
+    sync::{atomic::AtomicBool, Arc},
+    time::Instant,
+};
+
+use crate::config::Config;
+
+/// Application state handed to handlers via `State<AppState>`.
+#[derive(Clone)]
+pub struct AppState {
+    pub hostname: String,
+    /// `None` when the local IP couldn't be resolved (e.g. under a
+    /// network-namespace-restricted CNI); callers render "unknown" instead.
+    pub local_ip: Option<IpAddr>,
+    pub started_at: Instant,
+    pub config: Config,
+    /// The minijinja template source to render for `/`: the contents of
+    /// `config.template_path` if it was set and readable, otherwise
+    /// `crate::HTML`. Read once here so handlers never touch disk.
+    pub template: String,
+    pub ready: Arc<AtomicBool>,
+}
+
+impl AppState {
+    pub fn new(config: Config) -> Self {
+        let local_ip = local_ip_address::local_ip()
+            .inspect_err(|err| tracing::warn!("failed to determine local IP: {err}"))
+            .ok();
+        let template = config
+            .template_path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .unwrap_or_else(|| crate::HTML.to_string());
+
+        AppState {
+            hostname: gethostname::gethostname().to_string_lossy().to_string(),
+            local_ip,
+            started_at: Instant::now(),
+            config,
+            template,
+            ready: Arc::new(AtomicBool::new(true)),
+        }
+    }
+}